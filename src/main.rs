@@ -1,12 +1,31 @@
-use std::{fs, io, process::exit, sync::OnceLock};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Read},
+    process::exit,
+    sync::{Arc, Mutex, OnceLock},
+};
 
-use actix_web::{App, HttpServer, get, middleware, post, web};
+use actix_web::{
+    App, HttpServer,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    get,
+    middleware::{self, Next, from_fn},
+    post, web,
+};
 use anyhow::{Result, anyhow, bail};
-use camino::Utf8PathBuf;
+use arc_swap::{ArcSwap, ArcSwapOption};
+use base64::Engine;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::{Parser, Subcommand};
 use colored::Colorize;
 use dialoguer::Confirm;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::{WalkBuilder, overrides::OverrideBuilder};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use walkdir::WalkDir;
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -21,6 +40,17 @@ pub struct Config {
     pub file_paths_scrape_directory: Utf8PathBuf,
     /// Minimum log level to output
     pub log_level: String,
+    /// Extra glob patterns to include in `/getFilePaths`, on top of whatever
+    /// `.gitignore`/`.ignore` already let through
+    pub include_globs: Vec<String>,
+    /// Glob patterns to exclude from `/getFilePaths`, on top of
+    /// `.gitignore`/`.ignore`
+    pub exclude_globs: Vec<String>,
+    /// Whether to include binary files in `/getFilePaths`
+    pub include_binary: bool,
+    /// Require a local access token (see `Config::TOKEN_PATH`) on every
+    /// request. Disable only if you trust every process on this machine.
+    pub require_auth_token: bool,
 }
 
 impl Default for Config {
@@ -36,108 +66,599 @@ impl Default for Config {
             sourcemap_directory: ".".into(),
             file_paths_scrape_directory: ".".into(),
             log_level: "info".into(),
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            include_binary: false,
+            require_auth_token: true,
         }
     }
 }
 
-static CACHED_CONFIG: OnceLock<Config> = OnceLock::new();
+/// CLI overrides for [`Config`], mirroring its fields but optional so only
+/// the flags the user actually passes take effect.
+#[derive(Debug, Default, Clone, clap::Args)]
+pub struct ConfigOverride {
+    /// Override `project_name` from axosync.toml
+    #[arg(long)]
+    pub project_name: Option<String>,
+    /// Override `port` from axosync.toml
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Override `sourcemap_directory` from axosync.toml
+    #[arg(long)]
+    pub sourcemap_directory: Option<Utf8PathBuf>,
+    /// Override `file_paths_scrape_directory` from axosync.toml
+    #[arg(long)]
+    pub file_paths_scrape_directory: Option<Utf8PathBuf>,
+    /// Override `log_level` from axosync.toml
+    #[arg(long)]
+    pub log_level: Option<String>,
+    /// Override `include_globs` from axosync.toml (may be passed multiple times)
+    #[arg(long)]
+    pub include_globs: Option<Vec<String>>,
+    /// Override `exclude_globs` from axosync.toml (may be passed multiple times)
+    #[arg(long)]
+    pub exclude_globs: Option<Vec<String>>,
+    /// Override `include_binary` from axosync.toml
+    #[arg(long)]
+    pub include_binary: Option<bool>,
+    /// Override `require_auth_token` from axosync.toml
+    #[arg(long)]
+    pub require_auth_token: Option<bool>,
+}
+
+/// Copies every `Some` field of `other` onto `self`, leaving the rest untouched.
+pub trait Merge<T> {
+    fn merge(&mut self, other: T);
+}
+
+impl Merge<ConfigOverride> for Config {
+    fn merge(&mut self, other: ConfigOverride) {
+        let ConfigOverride {
+            project_name,
+            port,
+            sourcemap_directory,
+            file_paths_scrape_directory,
+            log_level,
+            include_globs,
+            exclude_globs,
+            include_binary,
+            require_auth_token,
+        } = other;
+
+        if let Some(project_name) = project_name {
+            self.project_name = project_name;
+        }
+        if let Some(port) = port {
+            self.port = port;
+        }
+        if let Some(sourcemap_directory) = sourcemap_directory {
+            self.sourcemap_directory = sourcemap_directory;
+        }
+        if let Some(file_paths_scrape_directory) = file_paths_scrape_directory {
+            self.file_paths_scrape_directory = file_paths_scrape_directory;
+        }
+        if let Some(log_level) = log_level {
+            self.log_level = log_level;
+        }
+        if let Some(include_globs) = include_globs {
+            self.include_globs = include_globs;
+        }
+        if let Some(exclude_globs) = exclude_globs {
+            self.exclude_globs = exclude_globs;
+        }
+        if let Some(include_binary) = include_binary {
+            self.include_binary = include_binary;
+        }
+        if let Some(require_auth_token) = require_auth_token {
+            self.require_auth_token = require_auth_token;
+        }
+    }
+}
+
+/// Precompiled glob/override filters derived from `Config::include_globs`/
+/// `exclude_globs`. Rebuilt only when the config is (re)loaded rather than on
+/// every `/getFilePaths` call, since compiling a `GlobSet`/`Override` is real
+/// work that the hot-reloadable config snapshot is meant to avoid repeating
+/// per request.
+struct CompiledFilters {
+    include_globset: GlobSet,
+    exclude_globset: GlobSet,
+    exclude_overrides: ignore::overrides::Override,
+}
+
+impl CompiledFilters {
+    fn compile(config: &Config) -> Result<CompiledFilters> {
+        let include_globset = build_globset(&config.include_globs)?;
+        let exclude_globset = build_globset(&config.exclude_globs)?;
+
+        // `exclude_globs` are layered on as negated overrides, which only
+        // ever narrows the normal .gitignore/.ignore-respecting walk. A
+        // *non*-negated override would instead flip the whole override set
+        // into an allowlist (see `ignore::overrides`), which is why
+        // `include_globs` are handled separately via `include_globset`
+        // rather than fed into this builder.
+        let mut overrides = OverrideBuilder::new(&config.file_paths_scrape_directory);
+        for glob in &config.exclude_globs {
+            overrides.add(&format!("!{glob}"))?;
+        }
+        let exclude_overrides = overrides.build()?;
+
+        Ok(CompiledFilters {
+            include_globset,
+            exclude_globset,
+            exclude_overrides,
+        })
+    }
+}
+
+/// A `Config` and the `CompiledFilters` derived from it, swapped in together
+/// so a reader never observes one half of a reload paired with the stale
+/// other half.
+struct ConfigSnapshot {
+    config: Arc<Config>,
+    filters: Arc<CompiledFilters>,
+}
+
+static CACHED_SNAPSHOT: OnceLock<ArcSwap<ConfigSnapshot>> = OnceLock::new();
+static CONFIG_OVERRIDES: OnceLock<ConfigOverride> = OnceLock::new();
 impl Config {
     pub const PATH: &str = "axosync.toml";
+    /// Where the per-run local access token is written, next to `PATH`
+    pub const TOKEN_PATH: &str = ".axosync-token";
+
+    fn read_from_disk(overrides: ConfigOverride) -> Result<Config> {
+        let content = match fs::read_to_string(Self::PATH) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                bail!(
+                    "{} was not found. Run `axosync init` to create one.",
+                    Self::PATH
+                );
+            }
+            Err(other) => bail!(other),
+        };
 
-    pub fn get() -> Result<Config> {
-        if let Some(config) = CACHED_CONFIG.get().cloned() {
-            return Ok(config);
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct ConfigToml {
+            config: Config,
         }
+        let ConfigToml { mut config } = toml::from_str(&content)?;
+        config.merge(overrides);
 
-        match fs::read_to_string(Self::PATH) {
-            Ok(content) => {
-                #[derive(Deserialize)]
-                #[serde(deny_unknown_fields)]
-                struct ConfigToml {
-                    config: Config,
-                }
-                let ConfigToml { config } = toml::from_str(&content)?;
-                if !config.sourcemap_directory.is_dir() {
-                    println!(
-                        "{} {} is not a valid directory.",
-                        "Warning (config.sourcemap_directory):"
-                            .bright_yellow()
-                            .bold(),
-                        config.sourcemap_directory
-                    );
-                }
-                if !config.file_paths_scrape_directory.is_dir() {
-                    println!(
-                        "{} {} is not a valid directory.",
-                        "Warning (config.file_paths_scrape_directory):"
-                            .bright_yellow()
-                            .bold(),
-                        config.file_paths_scrape_directory
-                    );
+        if !config.sourcemap_directory.is_dir() {
+            println!(
+                "{} {} is not a valid directory.",
+                "Warning (config.sourcemap_directory):"
+                    .bright_yellow()
+                    .bold(),
+                config.sourcemap_directory
+            );
+        }
+        if !config.file_paths_scrape_directory.is_dir() {
+            println!(
+                "{} {} is not a valid directory.",
+                "Warning (config.file_paths_scrape_directory):"
+                    .bright_yellow()
+                    .bold(),
+                config.file_paths_scrape_directory
+            );
+        }
+        Ok(config)
+    }
+
+    /// Reads `axosync.toml` for the first time, applies `overrides` on top of
+    /// it, and initializes the hot-reloadable cache. Only meant to be called
+    /// once, at startup.
+    pub fn load(overrides: ConfigOverride) -> Result<Arc<Config>> {
+        let config = Self::read_from_disk(overrides.clone())?;
+        let filters = CompiledFilters::compile(&config)?;
+        let config = Arc::new(config);
+
+        CONFIG_OVERRIDES.set(overrides).ok();
+        CACHED_SNAPSHOT
+            .set(ArcSwap::new(Arc::new(ConfigSnapshot {
+                config: config.clone(),
+                filters: Arc::new(filters),
+            })))
+            .ok();
+        Ok(config)
+    }
+
+    /// Returns the current config snapshot.
+    pub fn get() -> Arc<Config> {
+        Self::snapshot().config.clone()
+    }
+
+    /// Returns the glob/override filters compiled from the current config
+    /// snapshot's `include_globs`/`exclude_globs`.
+    fn filters() -> Arc<CompiledFilters> {
+        Self::snapshot().filters.clone()
+    }
+
+    /// Returns the current config and its derived filters together, as they
+    /// were paired at the last successful load/reload. Callers that need
+    /// both (e.g. `get_file_paths`) should load this once rather than
+    /// calling `get()`/`filters()` separately, since a reload between two
+    /// separate loads could otherwise pair a fresh `Config` with stale
+    /// filters or vice versa.
+    fn snapshot() -> Arc<ConfigSnapshot> {
+        CACHED_SNAPSHOT
+            .get()
+            .map(ArcSwap::load_full)
+            .unwrap_or_else(|| {
+                let config = Config::default();
+                let filters = CompiledFilters::compile(&config)
+                    .expect("default config filters always compile");
+                Arc::new(ConfigSnapshot {
+                    config: Arc::new(config),
+                    filters: Arc::new(filters),
+                })
+            })
+    }
+
+    /// Re-reads `axosync.toml` from disk and atomically swaps it into the
+    /// cache. Called by the file watcher when the file changes on disk; on a
+    /// parse error (including an invalid glob pattern), logs it and keeps the
+    /// previous snapshot.
+    pub fn reload() {
+        let overrides = CONFIG_OVERRIDES.get().cloned().unwrap_or_default();
+        let reloaded = Self::read_from_disk(overrides).and_then(|config| {
+            let filters = CompiledFilters::compile(&config)?;
+            Ok((config, filters))
+        });
+        match reloaded {
+            Ok((config, filters)) => {
+                let require_auth_token = config.require_auth_token;
+                if let Some(cached) = CACHED_SNAPSHOT.get() {
+                    cached.store(Arc::new(ConfigSnapshot {
+                        config: Arc::new(config),
+                        filters: Arc::new(filters),
+                    }));
                 }
-                CACHED_CONFIG.set(config.clone()).ok();
-                Ok(config)
+                sync_auth_token(require_auth_token);
+                // On its own thread: this runs inside the watcher's event
+                // handler, and (un)watching from there waits on that handler.
+                std::thread::spawn(rewatch_sourcemap_directory);
+                log::info!("Reloaded {}", Self::PATH);
             }
-            Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                let Config {
-                    project_name,
-                    port,
-                    sourcemap_directory,
-                    file_paths_scrape_directory,
-                    log_level,
-                } = Config::default();
-                let mut out = String::from(
-                    "#:schema https://raw.githubusercontent.com/angeld23/axosync/refs/heads/main/schema.json",
-                );
-                out.push_str("\n\n[config]");
-                {
-                    out.push_str(&format!("\nproject_name = {project_name:?}"));
-                    out.push_str(&format!("\nport = {port}"));
-                    out.push_str(&format!("\nsourcemap_directory = {sourcemap_directory:?}"));
-                    out.push_str(&format!(
-                        "\nfile_paths_scrape_directory = {file_paths_scrape_directory:?}"
-                    ));
-                    out.push_str(&format!("\nlog_level = {log_level:?}"));
+            Err(e) => log::error!("Failed to reload {}: {e}", Self::PATH),
+        }
+    }
+
+    /// Writes the default `axosync.toml` to the current directory, prompting
+    /// before overwriting an existing one.
+    pub fn init() -> Result<()> {
+        if Utf8PathBuf::from(Self::PATH).exists()
+            && !Confirm::new()
+                .with_prompt(
+                    format!("{} already exists. Overwrite it?", Self::PATH)
+                        .bold()
+                        .to_string(),
+                )
+                .default(false)
+                .show_default(true)
+                .interact()?
+        {
+            exit(0);
+        }
+
+        let Config {
+            project_name,
+            port,
+            sourcemap_directory,
+            file_paths_scrape_directory,
+            log_level,
+            include_globs,
+            exclude_globs,
+            include_binary,
+            require_auth_token,
+        } = Config::default();
+        let mut out = String::from(
+            "#:schema https://raw.githubusercontent.com/angeld23/axosync/refs/heads/main/schema.json",
+        );
+        out.push_str("\n\n[config]");
+        {
+            out.push_str(&format!("\nproject_name = {project_name:?}"));
+            out.push_str(&format!("\nport = {port}"));
+            out.push_str(&format!("\nsourcemap_directory = {sourcemap_directory:?}"));
+            out.push_str(&format!(
+                "\nfile_paths_scrape_directory = {file_paths_scrape_directory:?}"
+            ));
+            out.push_str(&format!("\nlog_level = {log_level:?}"));
+            out.push_str(&format!("\ninclude_globs = {include_globs:?}"));
+            out.push_str(&format!("\nexclude_globs = {exclude_globs:?}"));
+            out.push_str(&format!("\ninclude_binary = {include_binary}"));
+            out.push_str(&format!("\nrequire_auth_token = {require_auth_token}"));
+        }
+        fs::write(Self::PATH, out)?;
+
+        println!(
+            "Created {} in the current directory.",
+            Self::PATH.bright_blue().bold()
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum FileKind {
+    Text,
+    Binary,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScrapedFile {
+    path: Utf8PathBuf,
+    kind: FileKind,
+}
+
+/// Reads a small prefix of `path` and classifies it as text or binary.
+fn detect_file_kind(path: &Utf8Path) -> Result<FileKind> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = [0u8; 8192];
+    let bytes_read = file.read(&mut buffer)?;
+
+    Ok(
+        if content_inspector::inspect(&buffer[..bytes_read]).is_binary() {
+            FileKind::Binary
+        } else {
+            FileKind::Text
+        },
+    )
+}
+
+/// Builds a `GlobSet` from `patterns`, matched against paths relative to
+/// `file_paths_scrape_directory`.
+fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Returns the file's canonical path if `entry` is a regular file, or `None`
+/// for directories/symlinks/etc.
+fn canonicalize_entry(entry: ignore::DirEntry) -> Result<Option<Utf8PathBuf>> {
+    if !entry
+        .file_type()
+        .is_some_and(|file_type| file_type.is_file())
+    {
+        return Ok(None);
+    }
+    let path: Utf8PathBuf = entry.into_path().try_into()?;
+    Ok(Some(path.canonicalize_utf8()?))
+}
+
+#[get("/getFilePaths")]
+async fn get_file_paths() -> actix_web::Result<String> {
+    let snapshot = Config::snapshot();
+    let config = &snapshot.config;
+    let filters = &snapshot.filters;
+
+    let root = config
+        .file_paths_scrape_directory
+        .canonicalize_utf8()
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let mut files = Vec::<ScrapedFile>::new();
+    let mut seen = HashSet::<Utf8PathBuf>::new();
+
+    let walker = WalkBuilder::new(&config.file_paths_scrape_directory)
+        .overrides(filters.exclude_overrides.clone())
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+    for entry in walker {
+        let entry = entry.map_err(|e| actix_web::error::ErrorInternalServerError(anyhow!(e)))?;
+        // A file can vanish or become unreadable between being listed by the
+        // walker and being opened here, since this directory is actively
+        // being edited by the same plugin that's polling this endpoint.
+        // That's an expected transient, not a reason to fail the whole
+        // listing, so skip and log rather than propagating.
+        let canonical = match canonicalize_entry(entry) {
+            Ok(Some(canonical)) => canonical,
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Skipping entry in /getFilePaths: {e}");
+                continue;
+            }
+        };
+
+        let kind = match detect_file_kind(&canonical) {
+            Ok(kind) => kind,
+            Err(e) => {
+                log::warn!("Skipping {canonical} in /getFilePaths: {e}");
+                continue;
+            }
+        };
+        if kind == FileKind::Binary && !config.include_binary {
+            continue;
+        }
+
+        seen.insert(canonical.clone());
+        files.push(ScrapedFile {
+            path: canonical.as_str()[4..].replace("\\", "/").into(),
+            kind,
+        });
+    }
+
+    // `include_globs` add files back in on top of the ignore-respecting walk
+    // above, even ones `.gitignore`/`.ignore` would otherwise hide, so they
+    // need their own pass with every standard filter disabled.
+    if !config.include_globs.is_empty() {
+        let walker = WalkBuilder::new(&config.file_paths_scrape_directory)
+            .standard_filters(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker {
+            let entry =
+                entry.map_err(|e| actix_web::error::ErrorInternalServerError(anyhow!(e)))?;
+            let canonical = match canonicalize_entry(entry) {
+                Ok(Some(canonical)) => canonical,
+                Ok(None) => continue,
+                Err(e) => {
+                    log::warn!("Skipping entry in /getFilePaths: {e}");
+                    continue;
                 }
-                fs::write(Self::PATH, out)?;
+            };
+            if seen.contains(&canonical) {
+                continue;
+            }
 
-                println!(
-                    "Created {} in the current directory.",
-                    Self::PATH.bright_blue().bold()
-                );
-                println!("You can edit it before continuing if you wish.");
-                if !Confirm::new()
-                    .with_prompt("Continue?".bold().to_string())
-                    .default(true)
-                    .show_default(true)
-                    .interact()?
-                {
-                    exit(0);
+            let relative = canonical.strip_prefix(&root).unwrap_or(&canonical);
+            if filters.exclude_globset.is_match(relative)
+                || !filters.include_globset.is_match(relative)
+            {
+                continue;
+            }
+
+            let kind = match detect_file_kind(&canonical) {
+                Ok(kind) => kind,
+                Err(e) => {
+                    log::warn!("Skipping {canonical} in /getFilePaths: {e}");
+                    continue;
                 }
+            };
+            if kind == FileKind::Binary && !config.include_binary {
+                continue;
+            }
+
+            seen.insert(canonical.clone());
+            files.push(ScrapedFile {
+                path: canonical.as_str()[4..].replace("\\", "/").into(),
+                kind,
+            });
+        }
+    }
 
-                Self::get()
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(serde_json::to_string(&files)?)
+}
+
+/// Resolves `requested` against `config.file_paths_scrape_directory`,
+/// canonicalizing both sides and rejecting anything that escapes the root
+/// (e.g. via `../`).
+fn resolve_scrape_path(config: &Config, requested: &str) -> Result<Utf8PathBuf> {
+    let root = config.file_paths_scrape_directory.canonicalize_utf8()?;
+    let candidate = canonicalize_best_effort(&root.join(requested))?;
+
+    if !candidate.starts_with(&root) {
+        bail!("\"{requested}\" escapes file_paths_scrape_directory");
+    }
+
+    Ok(candidate)
+}
+
+/// Canonicalizes `path`, falling back to canonicalizing the nearest existing
+/// ancestor and rejoining the remainder for paths that don't exist on disk
+/// yet, such as a new file about to be written.
+fn canonicalize_best_effort(path: &Utf8Path) -> Result<Utf8PathBuf> {
+    if let Ok(canonical) = path.canonicalize_utf8() {
+        return Ok(canonical);
+    }
+
+    let mut remainder = Vec::new();
+    let mut current = path;
+    loop {
+        let Some(parent) = current.parent() else {
+            bail!("no existing ancestor directory for \"{path}\"");
+        };
+        match current.file_name() {
+            Some(name) => remainder.push(name.to_owned()),
+            None => bail!("\"{path}\" has a `..` below a directory that does not exist"),
+        }
+
+        if let Ok(canonical) = parent.canonicalize_utf8() {
+            let mut result = canonical;
+            for component in remainder.into_iter().rev() {
+                result.push(component);
             }
-            Err(other) => bail!(other),
+            return Ok(result);
         }
+        current = parent;
     }
 }
 
-#[get("/getFilePaths")]
-async fn get_file_paths() -> actix_web::Result<String> {
-    let config = Config::get().unwrap();
+#[derive(Debug, Deserialize)]
+struct GetFileContentsQuery {
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileContents {
+    contents: String,
+    base64: bool,
+}
 
-    let mut paths = Vec::<Utf8PathBuf>::new();
+#[get("/getFileContents")]
+async fn get_file_contents(query: web::Query<GetFileContentsQuery>) -> actix_web::Result<String> {
+    let config = Config::get();
+    let path =
+        resolve_scrape_path(&config, &query.path).map_err(actix_web::error::ErrorBadRequest)?;
 
-    for entry in WalkDir::new(config.file_paths_scrape_directory).sort_by_file_name() {
-        let path: Utf8PathBuf = entry.unwrap().into_path().try_into().unwrap();
-        paths.push(
-            path.canonicalize_utf8().unwrap().as_str()[4..]
-                .replace("\\", "/")
-                .into(),
-        );
+    let data = fs::read(&path).map_err(|e| actix_web::error::ErrorNotFound(anyhow!(e)))?;
+    let contents = match String::from_utf8(data) {
+        Ok(contents) => FileContents {
+            contents,
+            base64: false,
+        },
+        Err(e) => FileContents {
+            contents: base64::engine::general_purpose::STANDARD.encode(e.into_bytes()),
+            base64: true,
+        },
+    };
+
+    Ok(serde_json::to_string(&contents)?)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetFileContentsRequest {
+    path: String,
+    contents: String,
+    #[serde(default)]
+    base64: bool,
+}
+
+#[post("/setFileContents")]
+async fn set_file_contents(
+    requests: web::Json<Vec<SetFileContentsRequest>>,
+) -> actix_web::Result<()> {
+    let config = Config::get();
+
+    // Resolve and decode every entry up front so a single bad path/base64
+    // payload fails the whole batch before anything is written to disk,
+    // rather than leaving a half-applied batch behind.
+    let mut writes = Vec::<(Utf8PathBuf, Vec<u8>)>::new();
+    for req in requests.into_inner() {
+        let path =
+            resolve_scrape_path(&config, &req.path).map_err(actix_web::error::ErrorBadRequest)?;
+
+        let data = if req.base64 {
+            base64::engine::general_purpose::STANDARD
+                .decode(&req.contents)
+                .map_err(|e| actix_web::error::ErrorBadRequest(anyhow!(e)))?
+        } else {
+            req.contents.into_bytes()
+        };
+
+        writes.push((path, data));
+    }
+
+    for (path, data) in writes {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, data)?;
     }
 
-    Ok(serde_json::to_string(&paths)?)
+    Ok(())
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
@@ -153,13 +674,14 @@ pub struct SourcemapInstance {
     pub children: Vec<SourcemapInstance>,
 }
 
+static CACHED_SOURCEMAP: OnceLock<ArcSwap<SourcemapInstance>> = OnceLock::new();
 impl SourcemapInstance {
     pub fn path() -> Utf8PathBuf {
-        let config = Config::get().unwrap();
+        let config = Config::get();
         config.sourcemap_directory.join("sourcemap.json")
     }
 
-    pub fn load() -> Result<SourcemapInstance> {
+    fn read_from_disk() -> Result<SourcemapInstance> {
         let path = Self::path();
         if !path.exists() {
             Ok(SourcemapInstance::default())
@@ -169,13 +691,45 @@ impl SourcemapInstance {
         }
     }
 
+    /// Returns the current in-memory sourcemap tree, reading it from disk the
+    /// first time this is called.
+    pub fn load() -> Arc<SourcemapInstance> {
+        CACHED_SOURCEMAP
+            .get_or_init(|| ArcSwap::new(Arc::new(Self::read_from_disk().unwrap_or_default())))
+            .load_full()
+    }
+
+    /// Writes `self` through to disk and swaps it into the in-memory cache.
     pub fn save(&self) -> Result<()> {
         let path = Self::path();
         let data = serde_json::to_string_pretty(self)?;
         fs::write(path, data)?;
+
+        let instance = Arc::new(self.clone());
+        match CACHED_SOURCEMAP.get() {
+            Some(cached) => cached.store(instance),
+            None => {
+                CACHED_SOURCEMAP.get_or_init(|| ArcSwap::new(instance));
+            }
+        }
         Ok(())
     }
 
+    /// Re-reads `sourcemap.json` from disk and atomically swaps it into the
+    /// cache. Called by the file watcher when the file changes on disk; on a
+    /// parse error, logs it and keeps the previous snapshot.
+    pub fn reload() {
+        match Self::read_from_disk() {
+            Ok(instance) => {
+                CACHED_SOURCEMAP
+                    .get_or_init(|| ArcSwap::new(Arc::new(instance.clone())))
+                    .store(Arc::new(instance));
+                log::info!("Reloaded {}", Self::path());
+            }
+            Err(e) => log::error!("Failed to reload {}: {e}", Self::path()),
+        }
+    }
+
     pub fn find_first_child(&self, name: &str) -> Option<&SourcemapInstance> {
         self.children.iter().find(|child| child.name == name)
     }
@@ -197,7 +751,7 @@ pub struct SourcemapSetRequest {
 async fn sourcemap_set(requests: web::Json<Vec<SourcemapSetRequest>>) -> actix_web::Result<()> {
     let requests = requests.into_inner();
 
-    let mut top = SourcemapInstance::load().unwrap();
+    let mut top = (*SourcemapInstance::load()).clone();
     for req in requests {
         if req.path.is_empty() {
             top = req.value.ok_or_else(|| {
@@ -245,19 +799,246 @@ async fn sourcemap_set(requests: web::Json<Vec<SourcemapSetRequest>>) -> actix_w
 
 #[get("/getProjectFolderName")]
 async fn get_project_folder_name() -> String {
-    Config::get().unwrap().project_name
+    Config::get().project_name.clone()
+}
+
+/// Holds the current local access token, or `None` while `require_auth_token`
+/// is disabled. An `ArcSwapOption` (rather than a `OnceLock<String>`) so
+/// `sync_auth_token` can generate or clear it when `require_auth_token` is
+/// hot-reloaded, not just at startup.
+static AUTH_TOKEN: OnceLock<ArcSwapOption<String>> = OnceLock::new();
+
+/// Generates a fresh local access token, writes it to `Config::TOKEN_PATH`
+/// for the Studio plugin to pick up, and stores it for the auth middleware
+/// to check requests against.
+fn generate_auth_token() -> Result<()> {
+    let token: String = rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+
+    fs::write(Config::TOKEN_PATH, &token)?;
+    ensure_token_gitignored();
+    if let Some(cell) = AUTH_TOKEN.get() {
+        cell.store(Some(Arc::new(token)));
+    }
+
+    Ok(())
+}
+
+/// Generates or clears the local access token so a `require_auth_token`
+/// flag flipped via a hot-reloaded `axosync.toml` takes effect immediately,
+/// rather than staying fixed at whatever was configured at process start.
+fn sync_auth_token(require_auth_token: bool) {
+    let Some(cell) = AUTH_TOKEN.get() else {
+        return;
+    };
+    let currently_enabled = cell.load().is_some();
+
+    if require_auth_token && !currently_enabled {
+        match generate_auth_token() {
+            Ok(()) => log::info!("require_auth_token enabled; generated a new local access token"),
+            Err(e) => log::error!("Failed to generate local access token: {e}"),
+        }
+    } else if !require_auth_token && currently_enabled {
+        cell.store(None);
+        log::info!("require_auth_token disabled; no longer requiring a local access token");
+    }
+}
+
+/// Adds `Config::TOKEN_PATH` to `.gitignore`, if it isn't already listed.
+/// Creates `.gitignore` when it doesn't exist yet, since an un-ignored token
+/// file next to a fresh `axosync init` would otherwise go straight into the
+/// user's next commit.
+fn ensure_token_gitignored() {
+    let mut contents = fs::read_to_string(".gitignore").unwrap_or_default();
+    if contents
+        .lines()
+        .any(|line| line.trim() == Config::TOKEN_PATH)
+    {
+        return;
+    }
+
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(Config::TOKEN_PATH);
+    contents.push('\n');
+    fs::write(".gitignore", contents).ok();
+}
+
+/// Rejects any request without an `Authorization: Bearer <token>` header
+/// matching the current local access token. A no-op while `require_auth_token`
+/// is disabled (no token is currently set).
+async fn require_auth_token(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(token) = AUTH_TOKEN.get().and_then(ArcSwapOption::load_full) else {
+        return next.call(req).await;
+    };
+
+    let authorized = req
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| provided == token.as_str());
+
+    if !authorized {
+        return Err(actix_web::error::ErrorUnauthorized(anyhow!(
+            "missing or invalid Authorization header"
+        )));
+    }
+
+    next.call(req).await
+}
+
+/// The live file watcher, along with whichever non-`.` sourcemap parent
+/// directory it currently holds a watch on (if any), so `reload()` can
+/// re-home that watch when `sourcemap_directory` changes.
+struct FileWatcherState {
+    watcher: notify::RecommendedWatcher,
+    watched_sourcemap_parent: Option<Utf8PathBuf>,
+}
+
+static FILE_WATCHER: OnceLock<Mutex<FileWatcherState>> = OnceLock::new();
+
+/// Watches `axosync.toml` and `sourcemap.json` for external changes and
+/// reloads the corresponding cache when they're modified. The watcher is
+/// kept alive for the lifetime of the server in `FILE_WATCHER`.
+fn spawn_file_watcher() -> Result<()> {
+    let config_path = Utf8PathBuf::from(Config::PATH);
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) {
+            return;
+        }
+
+        // Re-derived on every event (not captured at startup) so a
+        // `sourcemap_directory` change picked up by `Config::reload()` is
+        // reflected here too, rather than matching against a stale name.
+        let sourcemap_name = SourcemapInstance::path().file_name().map(ToOwned::to_owned);
+        for path in &event.paths {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if name == config_path.as_str() {
+                Config::reload();
+            } else if sourcemap_name.as_deref() == Some(name) {
+                SourcemapInstance::reload();
+            }
+        }
+    })?;
+
+    watcher.watch(
+        Utf8Path::new(".").as_std_path(),
+        RecursiveMode::NonRecursive,
+    )?;
+
+    let sourcemap_path = SourcemapInstance::path();
+    let mut watched_sourcemap_parent = None;
+    if let Some(parent) = sourcemap_path.parent()
+        && parent != "."
+    {
+        watcher.watch(parent.as_std_path(), RecursiveMode::NonRecursive)?;
+        watched_sourcemap_parent = Some(parent.to_owned());
+    }
+
+    FILE_WATCHER
+        .set(Mutex::new(FileWatcherState {
+            watcher,
+            watched_sourcemap_parent,
+        }))
+        .map_err(|_| anyhow!("file watcher was already initialized"))?;
+
+    Ok(())
+}
+
+/// Re-homes the sourcemap watch onto the current `sourcemap_directory` when
+/// it differs from what's currently watched. Called after every successful
+/// `Config::reload()` so an external edit at the new location is picked up
+/// without restarting the server; a no-op when the directory is unchanged.
+fn rewatch_sourcemap_directory() {
+    let Some(state) = FILE_WATCHER.get() else {
+        return;
+    };
+    let Ok(mut state) = state.lock() else {
+        return;
+    };
+
+    let sourcemap_path = SourcemapInstance::path();
+    let new_parent = match sourcemap_path.parent() {
+        Some(parent) if parent != "." => Some(parent.to_owned()),
+        _ => None,
+    };
+    if new_parent == state.watched_sourcemap_parent {
+        return;
+    }
+
+    if let Some(old_parent) = state.watched_sourcemap_parent.take()
+        && let Err(e) = state.watcher.unwatch(old_parent.as_std_path())
+    {
+        log::warn!("Failed to unwatch {old_parent}: {e}");
+    }
+    if let Some(parent) = &new_parent {
+        match state
+            .watcher
+            .watch(parent.as_std_path(), RecursiveMode::NonRecursive)
+        {
+            Ok(()) => state.watched_sourcemap_parent = Some(parent.clone()),
+            Err(e) => log::warn!("Failed to watch {parent}: {e}"),
+        }
+    }
+}
+
+/// A Rojo-style sync server for Roblox Studio.
+#[derive(Debug, Parser)]
+#[command(name = "axosync", version, about)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    overrides: ConfigOverride,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Write the default axosync.toml to the current directory and exit
+    Init,
 }
 
 #[actix_web::main]
 async fn main() -> Result<()> {
-    let config = Config::get()?;
+    let args = Args::parse();
+
+    if let Some(Command::Init) = args.command {
+        return Config::init();
+    }
+
+    let config = Config::load(args.overrides)?;
     env_logger::init_from_env(env_logger::Env::new().default_filter_or(&config.log_level));
 
+    AUTH_TOKEN.set(ArcSwapOption::from(None)).ok();
+    if config.require_auth_token {
+        generate_auth_token()?;
+    }
+
+    // Lives in `FILE_WATCHER` for the duration of the server so its
+    // background watch thread keeps running.
+    spawn_file_watcher()?;
+
     HttpServer::new(|| {
         App::new()
             .service(get_file_paths)
+            .service(get_file_contents)
+            .service(set_file_contents)
             .service(sourcemap_set)
             .service(get_project_folder_name)
+            .wrap(from_fn(require_auth_token))
             .wrap(middleware::Logger::default())
     })
     .bind(("127.0.0.1", config.port))?